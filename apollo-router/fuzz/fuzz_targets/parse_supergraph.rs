@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Generates a supergraph SDL from the raw fuzzer input and runs it through `Schema::parse`,
+// the same way `parse_does_not_panic_on_generated_supergraphs` does in
+// `src/spec/schema.rs`, but driven by `cargo fuzz`'s corpus instead of a small fixed set of
+// seeds. `fuzz_parse_supergraph` is only `pub` under `cfg(fuzzing)`, which `cargo fuzz` sets,
+// so it isn't part of the crate's normal API surface.
+fuzz_target!(|data: &[u8]| {
+    apollo_router::fuzz_parse_supergraph(data);
+});
@@ -0,0 +1,22 @@
+//! Router configuration.
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+/// Top-level router configuration.
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields, default)]
+pub(crate) struct Configuration {
+    /// Controls what's visible to introspection, in addition to `@inaccessible`/`api_schema()`.
+    pub(crate) introspection_visibility: IntrospectionVisibilityConfig,
+}
+
+/// Explicit deny lists for introspection, layered on top of `@inaccessible`.
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields, default)]
+pub(crate) struct IntrospectionVisibilityConfig {
+    /// Type names to hide from introspection entirely.
+    pub(crate) hidden_types: Vec<String>,
+    /// `Type.field` coordinates to hide from introspection.
+    pub(crate) hidden_fields: Vec<String>,
+}
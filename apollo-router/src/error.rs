@@ -0,0 +1,244 @@
+//! Router error types.
+
+use std::fmt;
+
+/// Errors that can occur while parsing or composing a supergraph schema.
+#[derive(Debug)]
+pub(crate) enum SchemaError {
+    /// The schema failed to parse.
+    Parse(ParseErrors),
+    /// Composition (building the API schema from the supergraph) failed.
+    Api(Vec<SchemaDiagnostic>),
+    /// A subgraph in `join__Graph` does not specify a routing url.
+    MissingSubgraphUrl(String),
+    /// A subgraph's routing url could not be parsed as a URI.
+    UrlParse(String, http::uri::InvalidUri),
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::Parse(errors) => {
+                write!(f, "schema parse error: {errors}")
+            }
+            SchemaError::Api(diagnostics) => {
+                write!(
+                    f,
+                    "The supergraph schema failed to produce a valid API schema. Caused by:\n{}",
+                    join_diagnostic_messages(diagnostics)
+                )
+            }
+            SchemaError::MissingSubgraphUrl(name) => {
+                write!(f, "subgraph '{name}' does not specify a routing url")
+            }
+            SchemaError::UrlParse(name, err) => {
+                write!(f, "could not parse subgraph url for '{name}': {err}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+impl SchemaError {
+    /// Convenience constructor for a composition failure reported as a single message, for call
+    /// sites that don't have a richer [`SchemaDiagnostic`] to report.
+    pub(crate) fn api(message: impl Into<String>) -> Self {
+        SchemaError::Api(vec![SchemaDiagnostic {
+            code: None,
+            message: message.into(),
+            location: None,
+        }])
+    }
+
+    /// Like [`SchemaError::api`], but for call sites that can identify the specific failure as a
+    /// stable, machine-readable `code` rather than just a human message.
+    pub(crate) fn api_with_code(code: impl Into<String>, message: impl Into<String>) -> Self {
+        SchemaError::Api(vec![SchemaDiagnostic {
+            code: Some(code.into()),
+            message: message.into(),
+            location: None,
+        }])
+    }
+
+    /// The structured diagnostics behind this error, e.g. for tooling that wants to emit JSON
+    /// instead of scraping the `Display` output.
+    pub(crate) fn diagnostics(&self) -> Vec<SchemaDiagnostic> {
+        match self {
+            SchemaError::Parse(errors) => errors.to_diagnostics(),
+            SchemaError::Api(diagnostics) => diagnostics.clone(),
+            SchemaError::MissingSubgraphUrl(name) => vec![SchemaDiagnostic {
+                code: None,
+                message: format!("subgraph '{name}' does not specify a routing url"),
+                location: None,
+            }],
+            SchemaError::UrlParse(name, err) => vec![SchemaDiagnostic {
+                code: None,
+                message: format!("could not parse subgraph url for '{name}': {err}"),
+                location: None,
+            }],
+        }
+    }
+}
+
+fn join_diagnostic_messages(diagnostics: &[SchemaDiagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(|diagnostic| match &diagnostic.code {
+            Some(code) => format!("[{code}] {}", diagnostic.message),
+            None => diagnostic.message.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// A single machine-readable diagnostic produced while parsing or composing a schema: a
+/// human message plus, where the underlying tooling can provide them, a diagnostic code and a
+/// source location. Kept as typed data rather than pre-rendered text so tooling (e.g. CI
+/// surfacing JSON diagnostics) doesn't have to scrape a formatted string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SchemaDiagnostic {
+    pub(crate) code: Option<String>,
+    pub(crate) message: String,
+    pub(crate) location: Option<SourceLocation>,
+}
+
+/// A position within a schema source file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SourceLocation {
+    pub(crate) filename: Option<String>,
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+}
+
+/// The raw parse errors produced by the GraphQL compiler for one schema document, together with
+/// the source text they refer to so they can be rendered with context.
+#[derive(Debug)]
+pub(crate) struct ParseErrors {
+    pub(crate) raw_schema: String,
+    pub(crate) errors: Vec<apollo_compiler::ApolloDiagnostic>,
+}
+
+impl ParseErrors {
+    /// Prints the errors to stderr with the GraphQL compiler's own source-highlighted
+    /// formatting, for humans reading router logs.
+    pub(crate) fn print(&self) {
+        for error in &self.errors {
+            eprintln!("{error}");
+        }
+    }
+
+    /// Converts each parse error into a [`SchemaDiagnostic`], preserving the message as typed
+    /// data instead of the pre-rendered, source-highlighted text `print()` emits.
+    ///
+    /// `apollo_compiler::ApolloDiagnostic` doesn't expose a structured span accessor, only its
+    /// own source-highlighted `Display` rendering (the `GraphQL request:<line>:<column>` line
+    /// seen in that rendering). `location` is recovered by parsing that marker back out rather
+    /// than left `None`; if the rendering ever changes shape, `location` just goes back to
+    /// `None` instead of panicking.
+    pub(crate) fn to_diagnostics(&self) -> Vec<SchemaDiagnostic> {
+        self.errors
+            .iter()
+            .map(|error| {
+                let message = error.to_string();
+                let location = parse_source_location(&message);
+                SchemaDiagnostic {
+                    code: None,
+                    message,
+                    location,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Recovers a [`SourceLocation`] from the `<file>:<line>:<column>` marker that
+/// `apollo_compiler::ApolloDiagnostic`'s `Display` impl renders (e.g. `GraphQL request:42:1`).
+fn parse_source_location(rendered: &str) -> Option<SourceLocation> {
+    let line = rendered.lines().find_map(|line| {
+        let (filename, rest) = line.rsplit_once(':')?;
+        let (filename, line_no) = filename.rsplit_once(':')?;
+        let column: usize = rest.trim().parse().ok()?;
+        let line_no: usize = line_no.parse().ok()?;
+        Some((filename.to_string(), line_no, column))
+    })?;
+    Some(SourceLocation {
+        filename: Some(line.0),
+        line: line.1,
+        column: line.2,
+    })
+}
+
+impl fmt::Display for ParseErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rendered = self
+            .errors
+            .iter()
+            .map(|error| error.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        write!(f, "{rendered}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::schema::Schema;
+
+    #[test]
+    fn parse_source_location_recovers_line_and_column() {
+        let rendered = "some error\n\nGraphQL request:42:1\n41 |\n42 | input InputObject {";
+        let location = parse_source_location(rendered).unwrap();
+        assert_eq!(location.filename.as_deref(), Some("GraphQL request"));
+        assert_eq!(location.line, 42);
+        assert_eq!(location.column, 1);
+    }
+
+    #[test]
+    fn parse_source_location_is_none_without_a_marker() {
+        assert!(parse_source_location("just a plain message").is_none());
+    }
+
+    /// Exercises `parse_source_location` against a real `apollo_compiler::ApolloDiagnostic`
+    /// rendering (via a genuine `Schema::parse` failure), not just the hand-built string in
+    /// `parse_source_location_recovers_line_and_column` above, so a change to the compiler's
+    /// diagnostic format that breaks the heuristic shows up here instead of only in production.
+    #[test]
+    fn parse_error_diagnostics_include_a_source_location() {
+        let schema = "type Query { me: }";
+        let err = Schema::parse(schema, &Default::default()).unwrap_err();
+        assert!(matches!(err, SchemaError::Parse(_)));
+
+        let diagnostics = err.diagnostics();
+        assert!(!diagnostics.is_empty());
+        assert!(
+            diagnostics[0].location.is_some(),
+            "expected a source location to be recovered from a real parse diagnostic, got {:?}",
+            diagnostics[0]
+        );
+    }
+
+    #[test]
+    fn diagnostics_carries_the_code_into_the_rendered_message() {
+        let error = SchemaError::Api(vec![SchemaDiagnostic {
+            code: Some("DUPLICATE_SUBGRAPH_NAME".to_string()),
+            message: "must not have several subgraphs with same name 'accounts'".to_string(),
+            location: None,
+        }]);
+
+        assert_eq!(
+            error.to_string(),
+            "The supergraph schema failed to produce a valid API schema. Caused by:\n\
+             [DUPLICATE_SUBGRAPH_NAME] must not have several subgraphs with same name 'accounts'"
+        );
+        assert_eq!(
+            error.diagnostics(),
+            vec![SchemaDiagnostic {
+                code: Some("DUPLICATE_SUBGRAPH_NAME".to_string()),
+                message: "must not have several subgraphs with same name 'accounts'".to_string(),
+                location: None,
+            }]
+        );
+    }
+}
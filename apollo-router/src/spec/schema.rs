@@ -9,6 +9,8 @@ use apollo_compiler::hir;
 use apollo_compiler::ApolloCompiler;
 use apollo_compiler::AstDatabase;
 use apollo_compiler::HirDatabase;
+use apollo_parser::cst;
+use apollo_parser::Parser as GraphQLParser;
 use http::Uri;
 use itertools::Itertools;
 use router_bridge::api_schema;
@@ -16,6 +18,7 @@ use sha2::Digest;
 use sha2::Sha256;
 
 use crate::error::ParseErrors;
+use crate::error::SchemaDiagnostic;
 use crate::error::SchemaError;
 use crate::json_ext::Object;
 use crate::json_ext::Value;
@@ -38,22 +41,61 @@ pub(crate) struct Schema {
     api_schema: Option<Box<Schema>>,
     pub(crate) schema_id: Option<String>,
     root_operations: HashMap<OperationKind, String>,
+    entities: HashMap<String, Vec<KeySet>>,
+    visibility: IntrospectionVisibility,
 }
 
+fn as_string(value: &hir::Value) -> Option<&String> {
+    if let hir::Value::String(string) = value {
+        Some(string)
+    } else {
+        None
+    }
+}
+
+fn as_enum(value: &hir::Value) -> Option<&str> {
+    if let hir::Value::Enum(name) = value {
+        Some(name.src())
+    } else {
+        None
+    }
+}
+
+/// `router_bridge::api_schema::api_schema`'s composition errors only expose `.message`, not a
+/// machine-readable code, so [`SchemaDiagnostic::code`] stays `None` for the diagnostics built
+/// here — there's nothing to put in it without guessing. `collect_entity_keys` below, by
+/// contrast, builds its own [`SchemaError`]s directly and does attach a real code to each one,
+/// since it fully controls what each failure means.
 fn make_api_schema(schema: &str) -> Result<String, SchemaError> {
     let s = api_schema::api_schema(schema)
-        .map_err(|e| SchemaError::Api(e.to_string()))?
-        .map_err(|e| SchemaError::Api(e.iter().filter_map(|e| e.message.as_ref()).join(", ")))?;
+        .map_err(|e| SchemaError::api(e.to_string()))?
+        .map_err(|errors| {
+            SchemaError::Api(
+                errors
+                    .iter()
+                    .filter_map(|error| error.message.clone())
+                    .map(|message| SchemaDiagnostic {
+                        code: None,
+                        message,
+                        location: None,
+                    })
+                    .collect(),
+            )
+        })?;
     Ok(format!("{s}\n"))
 }
 
 impl Schema {
     pub(crate) fn parse(s: &str, configuration: &Configuration) -> Result<Self, SchemaError> {
-        let mut schema = parse(s, configuration)?;
-        schema.api_schema = Some(Box::new(parse(&make_api_schema(s)?, configuration)?));
+        let mut schema = parse(s, configuration, false)?;
+        schema.api_schema = Some(Box::new(parse(&make_api_schema(s)?, configuration, true)?));
         return Ok(schema);
 
-        fn parse(schema: &str, _configuration: &Configuration) -> Result<Schema, SchemaError> {
+        fn parse(
+            schema: &str,
+            configuration: &Configuration,
+            prune_invisible: bool,
+        ) -> Result<Schema, SchemaError> {
             let mut compiler = ApolloCompiler::new();
             compiler.add_type_system(
                 include_str!("introspection_types.graphql"),
@@ -78,29 +120,27 @@ impl Schema {
                 return Err(SchemaError::Parse(errors));
             }
 
-            fn as_string(value: &hir::Value) -> Option<&String> {
-                if let hir::Value::String(string) = value {
-                    Some(string)
-                } else {
-                    None
-                }
-            }
-
             let mut subgraphs = HashMap::new();
+            // Map from the `join__Graph` enum *value* (e.g. `ACCOUNTS`) to the subgraph name
+            // carried by its `@join__graph(name: ...)` directive (e.g. `accounts`), so that
+            // `@join__type(graph: ACCOUNTS, ...)` directives elsewhere in the document can be
+            // resolved back to the owning subgraph.
+            let mut graph_enum_values = HashMap::new();
             // TODO: error if not found?
             if let Some(join_enum) = compiler.db.find_enum_by_name("join__Graph".into()) {
-                for (name, url) in join_enum
-                    .enum_values_definition()
-                    .iter()
-                    .filter_map(|value| {
-                        let join_directive = value
-                            .directives()
-                            .iter()
-                            .find(|directive| directive.name() == "join__graph")?;
-                        let name = as_string(join_directive.argument_by_name("name")?)?;
-                        let url = as_string(join_directive.argument_by_name("url")?)?;
-                        Some((name, url))
-                    })
+                for (enum_value, name, url) in
+                    join_enum
+                        .enum_values_definition()
+                        .iter()
+                        .filter_map(|value| {
+                            let join_directive = value
+                                .directives()
+                                .iter()
+                                .find(|directive| directive.name() == "join__graph")?;
+                            let name = as_string(join_directive.argument_by_name("name")?)?;
+                            let url = as_string(join_directive.argument_by_name("url")?)?;
+                            Some((value.enum_value(), name, url))
+                        })
                 {
                     if url.is_empty() {
                         return Err(SchemaError::MissingSubgraphUrl(name.clone()));
@@ -108,35 +148,37 @@ impl Schema {
                     let url = Uri::from_str(url)
                         .map_err(|err| SchemaError::UrlParse(name.clone(), err))?;
                     if subgraphs.insert(name.clone(), url).is_some() {
-                        return Err(SchemaError::Api(format!(
-                            "must not have several subgraphs with same name '{name}'"
-                        )));
+                        return Err(SchemaError::api_with_code(
+                            "DUPLICATE_SUBGRAPH_NAME",
+                            format!("must not have several subgraphs with same name '{name}'"),
+                        ));
                     }
+                    graph_enum_values.insert(enum_value.to_owned(), name.clone());
                 }
             }
 
-            let object_types: HashMap<_, _> = compiler
+            let mut object_types: HashMap<_, _> = compiler
                 .db
                 .object_types()
                 .iter()
                 .map(|(name, def)| (name.clone(), (&**def).into()))
                 .collect();
 
-            let interfaces: HashMap<_, _> = compiler
+            let mut interfaces: HashMap<_, _> = compiler
                 .db
                 .interfaces()
                 .iter()
                 .map(|(name, def)| (name.clone(), (&**def).into()))
                 .collect();
 
-            let input_types: HashMap<_, _> = compiler
+            let mut input_types: HashMap<_, _> = compiler
                 .db
                 .input_objects()
                 .iter()
                 .map(|(name, def)| (name.clone(), (&**def).into()))
                 .collect();
 
-            let enums = compiler
+            let mut enums = compiler
                 .db
                 .enums()
                 .iter()
@@ -171,7 +213,7 @@ impl Schema {
                 })
                 .collect();
 
-            let custom_scalars = compiler
+            let mut custom_scalars = compiler
                 .db
                 .scalars()
                 .iter()
@@ -179,6 +221,26 @@ impl Schema {
                 .map(|(name, _def)| name.clone())
                 .collect();
 
+            let mut entities = HashMap::new();
+            for (name, def) in compiler.db.object_types().iter() {
+                collect_entity_keys(name, def.directives(), &graph_enum_values, &mut entities)?;
+            }
+            for (name, def) in compiler.db.interfaces().iter() {
+                collect_entity_keys(name, def.directives(), &graph_enum_values, &mut entities)?;
+            }
+
+            let visibility = IntrospectionVisibility::from_configuration(configuration);
+
+            if prune_invisible {
+                let reachable = visible_reachable_types(&compiler, &root_operations, &visibility);
+                let keep = |name: &String| reachable.contains(name) || is_introspection_type(name);
+                object_types.retain(|name, _| keep(name));
+                interfaces.retain(|name, _| keep(name));
+                input_types.retain(|name, _| keep(name));
+                enums.retain(|name, _| keep(name));
+                custom_scalars.retain(keep);
+            }
+
             let mut hasher = Sha256::new();
             hasher.update(schema.as_bytes());
             let schema_id = Some(format!("{:x}", hasher.finalize()));
@@ -195,6 +257,8 @@ impl Schema {
                 api_schema: None,
                 schema_id,
                 root_operations,
+                entities,
+                visibility,
             })
         }
     }
@@ -232,6 +296,632 @@ impl Schema {
             .map(|s| s.as_str())
             .unwrap_or_else(|| kind.as_str())
     }
+
+    /// Returns the Federation entity keys declared for `type_name`, one [`KeySet`] per
+    /// `@join__type(key:)` directive on it, so a subgraph that declares several keys contributes
+    /// several. Returns an empty slice for types that are not entities.
+    pub(crate) fn entity_keys(&self, type_name: &str) -> &[KeySet] {
+        self.entities
+            .get(type_name)
+            .map(|key_sets| key_sets.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Whether `type_name` is a Federation entity, i.e. at least one subgraph declares a
+    /// `@join__type(key:)` for it.
+    pub(crate) fn is_entity(&self, type_name: &str) -> bool {
+        self.entities.contains_key(type_name)
+    }
+
+    /// Whether `field_name` on `type_name` should be visible to introspection, per the
+    /// `Configuration`-driven deny lists (hidden types and hidden `Type.field` coordinates).
+    /// There is no allow list: anything not on a deny list is visible. This is in addition to,
+    /// and layered on top of, `@inaccessible`/`api_schema()`.
+    pub(crate) fn is_visible(&self, type_name: &str, field_name: &str) -> bool {
+        self.visibility.is_visible(type_name, field_name)
+    }
+
+    /// Re-serializes this schema into canonical SDL: types, fields, enum values, and
+    /// directives are emitted in a stable sorted order with consistent indentation and no
+    /// trailing whitespace. Two schemas that differ only in formatting or declaration order
+    /// export to identical canonical SDL, so it's suitable for diffing and change detection.
+    ///
+    /// `parse(export_sdl(s))` round-trips: re-parsing the canonical SDL produces a schema whose
+    /// own `export_sdl()` is byte-identical to this one's.
+    ///
+    /// Field arguments are rendered as part of their field (name and type, sorted), so two
+    /// schemas differing only in an argument's presence or type export to different canonical
+    /// SDL. Argument default values aren't rendered; see [`render_input_value_definitions`].
+    pub(crate) fn export_sdl(&self) -> String {
+        let mut compiler = ApolloCompiler::new();
+        compiler.add_type_system(
+            include_str!("introspection_types.graphql"),
+            "introspection_types.graphql",
+        );
+        compiler.add_type_system(self.raw_sdl.as_str(), "schema.graphql");
+
+        let mut blocks = Vec::new();
+
+        if !self.root_operations.is_empty() {
+            let mut operations: Vec<_> = self.root_operations.iter().collect();
+            operations.sort_by_key(|(kind, _)| kind.as_str());
+            let body = operations
+                .iter()
+                .map(|(kind, name)| format!("  {}: {name}", kind.as_str()))
+                .join("\n");
+            blocks.push(format!(
+                "schema{} {{\n{body}\n}}",
+                render_directives(compiler.db.schema().directives())
+            ));
+        }
+
+        for (name, def) in sorted_by_name(
+            compiler
+                .db
+                .directive_definitions()
+                .iter()
+                .filter(|(_name, def)| !def.is_built_in()),
+        ) {
+            let locations = def
+                .directive_locations()
+                .iter()
+                .map(|location| location.to_string())
+                .join(" | ");
+            let repeatable = if def.repeatable() { " repeatable" } else { "" };
+            blocks.push(format!(
+                "directive @{name}{}{repeatable} on {locations}",
+                render_input_value_definitions(def.arguments_definition())
+            ));
+        }
+
+        for (name, def) in sorted_by_name(
+            compiler
+                .db
+                .scalars()
+                .iter()
+                .filter(|(_name, def)| !def.is_built_in()),
+        ) {
+            let mut directives: Vec<hir::Directive> = def.directives().to_vec();
+            directives.extend(
+                def.extensions()
+                    .iter()
+                    .flat_map(|ext| ext.directives().iter().cloned()),
+            );
+            blocks.push(format!("scalar {name}{}", render_directives(&directives)));
+        }
+
+        for (name, def) in sorted_by_name(compiler.db.enums().iter()) {
+            let mut values: Vec<_> = def
+                .enum_values_definition()
+                .iter()
+                .chain(
+                    def.extensions()
+                        .iter()
+                        .flat_map(|ext| ext.enum_values_definition()),
+                )
+                .map(|value| value.enum_value().to_owned())
+                .collect();
+            values.sort();
+            values.dedup();
+            let body = values.iter().map(|value| format!("  {value}")).join("\n");
+
+            let mut directives: Vec<hir::Directive> = def.directives().to_vec();
+            directives.extend(
+                def.extensions()
+                    .iter()
+                    .flat_map(|ext| ext.directives().iter().cloned()),
+            );
+            blocks.push(format!(
+                "enum {name}{} {{\n{body}\n}}",
+                render_directives(&directives)
+            ));
+        }
+
+        for (name, def) in sorted_by_name(compiler.db.unions().iter()) {
+            let mut members: Vec<_> = def
+                .union_members()
+                .iter()
+                .chain(def.extensions().iter().flat_map(|ext| ext.union_members()))
+                .map(|member| member.name().to_owned())
+                .collect();
+            members.sort();
+            members.dedup();
+
+            let mut directives: Vec<hir::Directive> = def.directives().to_vec();
+            directives.extend(
+                def.extensions()
+                    .iter()
+                    .flat_map(|ext| ext.directives().iter().cloned()),
+            );
+            blocks.push(format!(
+                "union {name}{} = {}",
+                render_directives(&directives),
+                members.join(" | ")
+            ));
+        }
+
+        for (name, def) in sorted_by_name(compiler.db.input_objects().iter()) {
+            let mut fields: Vec<_> = def
+                .input_fields_definition()
+                .iter()
+                .chain(
+                    def.extensions()
+                        .iter()
+                        .flat_map(|ext| ext.input_fields_definition()),
+                )
+                .map(|field| {
+                    format!(
+                        "  {}: {}{}",
+                        field.name(),
+                        render_hir_type(field.ty()),
+                        render_directives(field.directives())
+                    )
+                })
+                .collect();
+            fields.sort();
+            blocks.push(format!(
+                "input {name}{} {{\n{}\n}}",
+                render_directives(def.directives()),
+                fields.join("\n")
+            ));
+        }
+
+        for (name, def) in sorted_by_name(compiler.db.interfaces().iter()) {
+            blocks.push(render_fielded_type(
+                "interface",
+                name,
+                def.directives(),
+                def.implements_interfaces()
+                    .iter()
+                    .chain(
+                        def.extensions()
+                            .iter()
+                            .flat_map(|ext| ext.implements_interfaces()),
+                    )
+                    .map(|i| i.interface())
+                    .collect(),
+                def.fields_definition()
+                    .iter()
+                    .chain(
+                        def.extensions()
+                            .iter()
+                            .flat_map(|ext| ext.fields_definition()),
+                    )
+                    .map(|field| {
+                        (
+                            field.name(),
+                            field.arguments_definition(),
+                            field.ty(),
+                            field.directives(),
+                        )
+                    })
+                    .collect(),
+            ));
+        }
+
+        for (name, def) in sorted_by_name(compiler.db.object_types().iter()) {
+            blocks.push(render_fielded_type(
+                "type",
+                name,
+                def.directives(),
+                def.implements_interfaces()
+                    .iter()
+                    .chain(
+                        def.extensions()
+                            .iter()
+                            .flat_map(|ext| ext.implements_interfaces()),
+                    )
+                    .map(|i| i.interface())
+                    .collect(),
+                def.fields_definition()
+                    .iter()
+                    .chain(
+                        def.extensions()
+                            .iter()
+                            .flat_map(|ext| ext.fields_definition()),
+                    )
+                    .map(|field| {
+                        (
+                            field.name(),
+                            field.arguments_definition(),
+                            field.ty(),
+                            field.directives(),
+                        )
+                    })
+                    .collect(),
+            ));
+        }
+
+        blocks.sort();
+        format!("{}\n", blocks.join("\n\n"))
+    }
+
+    /// A SHA-256 hash of [`Schema::export_sdl`], i.e. a `schema_id`-like fingerprint that is
+    /// robust to whitespace/field-ordering differences between otherwise-identical supergraphs.
+    pub(crate) fn canonical_id(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.export_sdl().as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+fn sorted_by_name<'a, V>(
+    entries: impl Iterator<Item = (&'a String, &'a V)>,
+) -> Vec<(&'a String, &'a V)> {
+    let mut entries: Vec<_> = entries.collect();
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    entries
+}
+
+fn render_fielded_type(
+    keyword: &str,
+    name: &str,
+    directives: &[hir::Directive],
+    mut implements: Vec<&str>,
+    fields: Vec<(
+        &str,
+        &hir::ArgumentsDefinition,
+        &hir::Type,
+        &[hir::Directive],
+    )>,
+) -> String {
+    implements.sort_unstable();
+    implements.dedup();
+    let implements_clause = if implements.is_empty() {
+        String::new()
+    } else {
+        format!(" implements {}", implements.join(" & "))
+    };
+
+    let mut rendered_fields: Vec<_> = fields
+        .into_iter()
+        .map(|(field_name, arguments, ty, field_directives)| {
+            format!(
+                "  {field_name}{}: {}{}",
+                render_input_value_definitions(arguments),
+                render_hir_type(ty),
+                render_directives(field_directives)
+            )
+        })
+        .collect();
+    rendered_fields.sort();
+
+    format!(
+        "{keyword} {name}{implements_clause}{} {{\n{}\n}}",
+        render_directives(directives),
+        rendered_fields.join("\n")
+    )
+}
+
+/// Renders a field or directive's arguments, sorted by name, e.g. `(filter: UserFilter)`. Returns
+/// the empty string if there are none. Default values aren't rendered, so two schemas differing
+/// only in an argument's default value still export to the same canonical SDL.
+fn render_input_value_definitions(arguments: &hir::ArgumentsDefinition) -> String {
+    let mut args: Vec<_> = arguments
+        .input_values()
+        .iter()
+        .map(|arg| {
+            format!(
+                "{}: {}{}",
+                arg.name(),
+                render_hir_type(arg.ty()),
+                render_directives(arg.directives())
+            )
+        })
+        .collect();
+    args.sort();
+
+    if args.is_empty() {
+        String::new()
+    } else {
+        format!("({})", args.join(", "))
+    }
+}
+
+fn render_hir_type(ty: &hir::Type) -> String {
+    match ty {
+        hir::Type::Named { name, .. } => name.clone(),
+        hir::Type::NonNull { ty, .. } => format!("{}!", render_hir_type(ty)),
+        hir::Type::List { ty, .. } => format!("[{}]", render_hir_type(ty)),
+    }
+}
+
+/// Renders a directive's arguments into canonical GraphQL syntax. Only the value shapes the
+/// Federation join directives actually use (string, enum, boolean) are supported; anything else
+/// is dropped from the canonical form rather than guessed at.
+fn render_directive_argument_value(value: &hir::Value) -> Option<String> {
+    if let Some(s) = as_string(value) {
+        return Some(format!("{s:?}"));
+    }
+    if let Some(e) = as_enum(value) {
+        return Some(e.to_owned());
+    }
+    if let hir::Value::Boolean(b) = value {
+        return Some(b.to_string());
+    }
+    None
+}
+
+fn render_directive(directive: &hir::Directive) -> String {
+    let mut args: Vec<_> = directive
+        .arguments()
+        .iter()
+        .filter_map(|arg| {
+            let value = render_directive_argument_value(arg.value())?;
+            Some(format!("{}: {value}", arg.name()))
+        })
+        .collect();
+    args.sort();
+
+    if args.is_empty() {
+        format!("@{}", directive.name())
+    } else {
+        format!("@{}({})", directive.name(), args.join(", "))
+    }
+}
+
+/// Renders a type or field's directives, sorted by name, prefixed with a single space so it can
+/// be appended directly after the name/type it annotates.
+fn render_directives(directives: &[hir::Directive]) -> String {
+    let mut rendered: Vec<_> = directives.iter().map(render_directive).collect();
+    rendered.sort();
+    if rendered.is_empty() {
+        String::new()
+    } else {
+        format!(" {}", rendered.join(" "))
+    }
+}
+
+/// Runtime-configured introspection visibility: explicit deny lists of type names and
+/// `Type.field` coordinates. Layered on top of `@inaccessible`/`api_schema()`, which is
+/// computed separately by the router bridge.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct IntrospectionVisibility {
+    hidden_types: HashSet<String>,
+    // "Type.field" coordinates
+    hidden_fields: HashSet<String>,
+}
+
+impl IntrospectionVisibility {
+    fn from_configuration(configuration: &Configuration) -> Self {
+        IntrospectionVisibility {
+            hidden_types: configuration
+                .introspection_visibility
+                .hidden_types
+                .iter()
+                .cloned()
+                .collect(),
+            hidden_fields: configuration
+                .introspection_visibility
+                .hidden_fields
+                .iter()
+                .cloned()
+                .collect(),
+        }
+    }
+
+    fn is_visible(&self, type_name: &str, field_name: &str) -> bool {
+        !self.hidden_types.contains(type_name)
+            && !self
+                .hidden_fields
+                .contains(&format!("{type_name}.{field_name}"))
+    }
+}
+
+/// Built-in introspection types (`__Schema`, `__Type`, ...) are always retained regardless of
+/// reachability: they aren't reachable by walking the user schema's own root operation types,
+/// but introspection needs them to describe itself.
+fn is_introspection_type(name: &str) -> bool {
+    name.starts_with("__")
+}
+
+/// Depth-first walk from the root operation types, following only visible fields, to find
+/// every type that introspection can actually reach. Mirrors the pass async-graphql runs after
+/// marking fields/types invisible: hidden types are never reached, and anything not reached
+/// this way gets pruned from the API schema's introspection-facing collections.
+///
+/// Both a field's return type and its argument types are followed, so input types that are
+/// only reachable via an argument are kept too.
+fn visible_reachable_types(
+    compiler: &ApolloCompiler,
+    root_operations: &HashMap<OperationKind, String>,
+    visibility: &IntrospectionVisibility,
+) -> HashSet<String> {
+    let mut reachable = HashSet::new();
+    let mut frontier: Vec<String> = root_operations.values().cloned().collect();
+
+    while let Some(type_name) = frontier.pop() {
+        if visibility.hidden_types.contains(&type_name) {
+            continue;
+        }
+        if !reachable.insert(type_name.clone()) {
+            continue;
+        }
+
+        if let Some(def) = compiler.db.find_object_type_by_name(type_name.clone()) {
+            for field in def.fields_definition() {
+                if !visibility.is_visible(&type_name, field.name()) {
+                    continue;
+                }
+                frontier.push(named_hir_type(field.ty()).to_owned());
+                frontier.extend(
+                    field
+                        .arguments_definition()
+                        .input_values()
+                        .iter()
+                        .map(|arg| named_hir_type(arg.ty()).to_owned()),
+                );
+            }
+        }
+        if let Some(def) = compiler.db.find_interface_by_name(type_name.clone()) {
+            for field in def.fields_definition() {
+                if !visibility.is_visible(&type_name, field.name()) {
+                    continue;
+                }
+                frontier.push(named_hir_type(field.ty()).to_owned());
+                frontier.extend(
+                    field
+                        .arguments_definition()
+                        .input_values()
+                        .iter()
+                        .map(|arg| named_hir_type(arg.ty()).to_owned()),
+                );
+            }
+        }
+        if let Some(def) = compiler.db.find_input_object_by_name(type_name.clone()) {
+            frontier.extend(def.input_fields_definition().iter().filter_map(|field| {
+                visibility
+                    .is_visible(&type_name, field.name())
+                    .then(|| named_hir_type(field.ty()).to_owned())
+            }));
+        }
+
+        if let Some(subtypes) = compiler.db.type_system().subtype_map.get(&type_name) {
+            frontier.extend(subtypes.iter().cloned());
+        }
+    }
+
+    reachable
+}
+
+fn named_hir_type(ty: &hir::Type) -> &str {
+    match ty {
+        hir::Type::Named { name, .. } => name,
+        hir::Type::NonNull { ty, .. } | hir::Type::List { ty, .. } => named_hir_type(ty),
+    }
+}
+
+/// A single field selected by a Federation `@key`, e.g. `id` in `@join__type(key: "id")`, or
+/// `a` with nested `b`/`c` in `@join__type(key: "a { b c }")`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct KeyField {
+    pub(crate) name: String,
+    pub(crate) selections: Vec<KeyField>,
+}
+
+/// The parsed `key` selection for one type in one subgraph, as declared by a
+/// `@join__type(graph:, key:)` directive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct KeySet {
+    /// The name of the subgraph that owns this key (the `name` argument of the matching
+    /// `@join__graph`, not the `join__Graph` enum value).
+    pub(crate) graph: String,
+    pub(crate) fields: Vec<KeyField>,
+}
+
+/// Walks the `@join__type(graph:, key:)` directives on a type definition and records one
+/// [`KeySet`] per directive that carries a non-empty `key`.
+fn collect_entity_keys(
+    type_name: &str,
+    directives: &[hir::Directive],
+    graph_enum_values: &HashMap<String, String>,
+    entities: &mut HashMap<String, Vec<KeySet>>,
+) -> Result<(), SchemaError> {
+    for directive in directives.iter().filter(|d| d.name() == "join__type") {
+        let key_arg = match directive.argument_by_name("key") {
+            Some(value) => value,
+            // `@join__type` without a `key` just declares that the subgraph contributes to
+            // the type, it is not an entity key.
+            None => continue,
+        };
+
+        let raw_key = as_string(key_arg)
+            .map(|s| s.as_str())
+            .filter(|s| !s.trim().is_empty())
+            .ok_or_else(|| {
+                SchemaError::api_with_code(
+                    "INVALID_KEY_ARGUMENT",
+                    format!(
+                        "`{type_name}` has an empty or invalid `key` argument in `@join__type`"
+                    ),
+                )
+            })?;
+
+        let fields = parse_key_selection(raw_key).ok_or_else(|| {
+            SchemaError::api_with_code(
+                "INVALID_KEY_SELECTION",
+                format!("could not parse `key` selection `{raw_key}` for type `{type_name}`"),
+            )
+        })?;
+
+        let graph = directive
+            .argument_by_name("graph")
+            .and_then(as_enum)
+            .and_then(|value| graph_enum_values.get(value))
+            .cloned()
+            .ok_or_else(|| {
+                SchemaError::api_with_code(
+                    "UNKNOWN_JOIN_GRAPH",
+                    format!(
+                        "`{type_name}` has a `@join__type` with a missing or unknown `graph` argument"
+                    ),
+                )
+            })?;
+
+        entities
+            .entry(type_name.to_owned())
+            .or_insert_with(Vec::new)
+            .push(KeySet { graph, fields });
+    }
+
+    Ok(())
+}
+
+/// Parses a Federation `@key` field-selection string, e.g. `"id"` or `"a b c { v }"`, into
+/// the nested [`KeyField`] shape, so it can later be matched against `_entities`
+/// representation objects field by field.
+///
+/// A `key` string is a bare GraphQL selection set with the outer braces omitted (it selects
+/// fields on the entity type, not on `Query`, so it can't be validated against the schema the
+/// way an operation's selection set is). Wrapping it in `{ ... }` and handing it to the same
+/// `apollo-parser` CST parser the rest of the GraphQL tooling in this crate is built on gets us
+/// real selection-set grammar (nesting, whitespace, comments) for free, instead of a bespoke
+/// tokenizer.
+fn parse_key_selection(input: &str) -> Option<Vec<KeyField>> {
+    // The closing brace goes on its own line so a trailing `#` comment can't swallow it.
+    let document = format!("{{ {input}\n}}");
+    let tree = GraphQLParser::new(&document).parse();
+    if tree.errors().next().is_some() {
+        return None;
+    }
+
+    let selection_set = tree.document().definitions().find_map(|definition| {
+        if let cst::Definition::OperationDefinition(operation) = definition {
+            operation.selection_set()
+        } else {
+            None
+        }
+    })?;
+
+    let fields = key_fields_from_selection_set(&selection_set)?;
+    if fields.is_empty() {
+        return None;
+    }
+    Some(fields)
+}
+
+/// Converts a parsed CST selection set into the nested [`KeyField`] shape. `@key` selections
+/// are plain field paths, so a fragment spread or inline fragment anywhere in the set is
+/// treated as malformed.
+fn key_fields_from_selection_set(selection_set: &cst::SelectionSet) -> Option<Vec<KeyField>> {
+    selection_set
+        .selections()
+        .map(|selection| {
+            let field = match selection {
+                cst::Selection::Field(field) => field,
+                cst::Selection::FragmentSpread(_) | cst::Selection::InlineFragment(_) => {
+                    return None
+                }
+            };
+
+            let name = field.name()?.text().to_string();
+            let selections = match field.selection_set() {
+                Some(nested) => key_fields_from_selection_set(&nested)?,
+                None => Vec::new(),
+            };
+            Some(KeyField { name, selections })
+        })
+        .collect()
 }
 
 #[derive(Debug)]
@@ -332,6 +1022,7 @@ impl From<&'_ hir::InputObjectTypeDefinition> for InputObjectType {
 
 #[cfg(test)]
 mod tests {
+    use super::fuzz_support::generate_supergraph_sdl;
     use super::*;
 
     fn with_supergraph_boilerplate(content: &str) -> String {
@@ -502,6 +1193,145 @@ mod tests {
         assert_eq!(schema.subgraphs.get("test"), None);
     }
 
+    #[test]
+    fn entity_keys_are_extracted_from_join_type_directives() {
+        let schema = r#"
+        schema
+          @core(feature: "https://specs.apollo.dev/core/v0.1"),
+          @core(feature: "https://specs.apollo.dev/join/v0.1")
+        {
+          query: Query
+        }
+        directive @core(feature: String!) repeatable on SCHEMA
+        directive @join__graph(name: String!, url: String!) on ENUM_VALUE
+        directive @join__type(graph: join__Graph!, key: String) repeatable on OBJECT | INTERFACE
+
+        enum join__Graph {
+            ACCOUNTS @join__graph(name: "accounts", url: "http://localhost:4001/graphql")
+            INVENTORY @join__graph(name: "inventory", url: "http://localhost:4004/graphql")
+        }
+        type Query {
+          me: String
+        }
+        type User
+          @join__type(graph: ACCOUNTS, key: "id")
+          @join__type(graph: INVENTORY, key: "id sku { variation { id } }")
+        {
+          id: ID
+          sku: Sku
+        }
+        type Sku {
+          variation: Variation
+        }
+        type Variation {
+          id: ID
+        }
+        type NotAnEntity {
+          id: ID
+        }
+        "#;
+        let schema = Schema::parse(schema, &Default::default()).unwrap();
+
+        assert!(schema.is_entity("User"));
+        assert!(!schema.is_entity("NotAnEntity"));
+        assert!(!schema.is_entity("Query"));
+
+        let keys = schema.entity_keys("User");
+        assert_eq!(keys.len(), 2);
+
+        let accounts_key = keys.iter().find(|k| k.graph == "accounts").unwrap();
+        assert_eq!(
+            accounts_key.fields,
+            vec![KeyField {
+                name: "id".to_string(),
+                selections: vec![],
+            }]
+        );
+
+        let inventory_key = keys.iter().find(|k| k.graph == "inventory").unwrap();
+        assert_eq!(
+            inventory_key.fields,
+            vec![
+                KeyField {
+                    name: "id".to_string(),
+                    selections: vec![],
+                },
+                KeyField {
+                    name: "sku".to_string(),
+                    selections: vec![KeyField {
+                        name: "variation".to_string(),
+                        selections: vec![KeyField {
+                            name: "id".to_string(),
+                            selections: vec![],
+                        }],
+                    }],
+                },
+            ]
+        );
+
+        assert!(schema.entity_keys("NotAnEntity").is_empty());
+    }
+
+    #[test]
+    fn malformed_key_selection_is_a_schema_error() {
+        let schema = r#"
+        schema
+          @core(feature: "https://specs.apollo.dev/core/v0.1"),
+          @core(feature: "https://specs.apollo.dev/join/v0.1")
+        {
+          query: Query
+        }
+        directive @core(feature: String!) repeatable on SCHEMA
+        directive @join__graph(name: String!, url: String!) on ENUM_VALUE
+        directive @join__type(graph: join__Graph!, key: String) repeatable on OBJECT
+
+        enum join__Graph {
+            ACCOUNTS @join__graph(name: "accounts", url: "http://localhost:4001/graphql")
+        }
+        type Query {
+          me: String
+        }
+        type User @join__type(graph: ACCOUNTS, key: "id { unterminated") {
+          id: ID
+        }
+        "#;
+        assert!(matches!(
+            Schema::parse(schema, &Default::default()),
+            Err(SchemaError::Api(_))
+        ));
+    }
+
+    #[test]
+    fn missing_graph_argument_is_a_schema_error() {
+        let schema = r#"
+        schema
+          @core(feature: "https://specs.apollo.dev/core/v0.1"),
+          @core(feature: "https://specs.apollo.dev/join/v0.1")
+        {
+          query: Query
+        }
+        directive @core(feature: String!) repeatable on SCHEMA
+        directive @join__graph(name: String!, url: String!) on ENUM_VALUE
+        directive @join__type(graph: join__Graph, key: String) repeatable on OBJECT
+
+        enum join__Graph {
+            ACCOUNTS @join__graph(name: "accounts", url: "http://localhost:4001/graphql")
+        }
+        type Query {
+          me: String
+        }
+        type User @join__type(key: "id") {
+          id: ID
+        }
+        "#;
+        let err = Schema::parse(schema, &Default::default()).unwrap_err();
+        assert!(matches!(err, SchemaError::Api(_)));
+        assert_eq!(
+            err.diagnostics()[0].code.as_deref(),
+            Some("UNKNOWN_JOIN_GRAPH")
+        );
+    }
+
     #[test]
     fn api_schema() {
         let schema = include_str!("../testdata/contract_schema.graphql");
@@ -516,6 +1346,108 @@ mod tests {
             .is_none());
     }
 
+    #[test]
+    fn introspection_visibility_prunes_types_only_reachable_through_a_hidden_field() {
+        let schema = with_supergraph_boilerplate(
+            r#"
+            type Query {
+                me: String
+                hidden: Hidden
+            }
+            type Hidden {
+                value: String
+            }
+            "#,
+        );
+
+        let mut configuration = Configuration::default();
+        configuration.introspection_visibility.hidden_fields = vec!["Query.hidden".to_string()];
+
+        let schema = Schema::parse(&schema, &configuration).unwrap();
+
+        // The full schema still has `Hidden`...
+        assert!(schema.object_types.contains_key("Hidden"));
+        // ...but with `Query.hidden` invisible, the API schema can no longer reach it.
+        assert!(!schema.api_schema().object_types.contains_key("Hidden"));
+    }
+
+    #[test]
+    fn introspection_visibility_keeps_builtin_introspection_types() {
+        let schema = with_supergraph_boilerplate(
+            r#"
+            type Query {
+                me: String
+                hidden: Hidden
+            }
+            type Hidden {
+                value: String
+            }
+            "#,
+        );
+
+        let mut configuration = Configuration::default();
+        configuration.introspection_visibility.hidden_types = vec!["Hidden".to_string()];
+
+        let schema = Schema::parse(&schema, &configuration).unwrap();
+        let api_schema = schema.api_schema();
+
+        assert!(!api_schema.object_types.contains_key("Hidden"));
+        // Built-in introspection types aren't reachable from `Query` either, but must survive
+        // the same pruning pass so introspection can still describe itself.
+        assert!(api_schema.object_types.contains_key("__Schema"));
+    }
+
+    #[test]
+    fn introspection_visibility_keeps_input_types_only_reachable_through_an_argument() {
+        let schema = with_supergraph_boilerplate(
+            r#"
+            type Query {
+                users(filter: UserFilter): String
+            }
+            input UserFilter {
+                name: String
+            }
+            "#,
+        );
+
+        let schema = Schema::parse(&schema, &Configuration::default()).unwrap();
+
+        // `UserFilter` is never a field's return type, only an argument type, so it's only
+        // reachable if the pruning pass walks argument types as well as return types.
+        assert!(schema.api_schema().input_types.contains_key("UserFilter"));
+    }
+
+    #[test]
+    fn non_api_schema_retains_everything_the_api_schema_prunes() {
+        let schema = with_supergraph_boilerplate(
+            r#"
+            type Query {
+                me: String
+                hidden: Hidden
+            }
+            type Hidden {
+                value: String
+            }
+            "#,
+        );
+
+        let mut configuration = Configuration::default();
+        configuration.introspection_visibility.hidden_fields = vec!["Query.hidden".to_string()];
+
+        let schema = Schema::parse(&schema, &configuration).unwrap();
+
+        for name in schema.api_schema().object_types.keys() {
+            assert!(
+                schema.object_types.contains_key(name),
+                "non-api schema is missing `{name}`, which the api schema kept"
+            );
+        }
+        assert!(
+            schema.object_types.len() > schema.api_schema().object_types.len(),
+            "api schema should have pruned at least one type that the non-api schema keeps"
+        );
+    }
+
     #[test]
     fn schema_id() {
         #[cfg(not(windows))]
@@ -544,9 +1476,9 @@ mod tests {
     fn inaccessible_on_non_core() {
         let schema = include_str!("../testdata/inaccessible_on_non_core.graphql");
         match Schema::parse(schema, &Default::default()) {
-            Err(SchemaError::Api(s)) => {
+            Err(err @ SchemaError::Api(_)) => {
                 assert_eq!(
-                    s,
+                    err.to_string(),
                     r#"The supergraph schema failed to produce a valid API schema. Caused by:
 Input field "InputObject.privateField" is @inaccessible but is used in the default value of "@foo(someArg:)", which is in the API schema.
 
@@ -568,4 +1500,358 @@ GraphQL request:42:1
         let result = Schema::parse(schema, &Default::default());
         assert!(result.is_err());
     }
+
+    #[test]
+    fn export_sdl_is_formatting_independent() {
+        let compact = with_supergraph_boilerplate(
+            r#"type Query { b: String a: String }
+            type Foo { me: String }"#,
+        );
+        let spread_out = with_supergraph_boilerplate(
+            r#"
+            type Query {
+                a: String
+                b: String
+            }
+
+            type Foo {
+                me: String
+            }
+            "#,
+        );
+
+        let compact = Schema::parse(&compact, &Default::default()).unwrap();
+        let spread_out = Schema::parse(&spread_out, &Default::default()).unwrap();
+
+        assert_eq!(compact.export_sdl(), spread_out.export_sdl());
+        assert_eq!(compact.canonical_id(), spread_out.canonical_id());
+    }
+
+    #[test]
+    fn export_sdl_round_trips() {
+        let schema = with_supergraph_boilerplate(
+            r#"
+            type Query {
+                me: String
+            }
+            "#,
+        );
+        let schema = Schema::parse(&schema, &Default::default()).unwrap();
+        let exported = schema.export_sdl();
+
+        let reparsed = Schema::parse(&exported, &Default::default()).unwrap();
+        assert_eq!(exported, reparsed.export_sdl());
+    }
+
+    #[test]
+    fn export_sdl_includes_unions() {
+        let schema = with_supergraph_boilerplate(
+            r#"
+            type Query {
+                me: String
+            }
+            type Foo {
+                me: String
+            }
+            type Bar {
+                me: String
+            }
+            union FooOrBar = Foo | Bar
+            "#,
+        );
+        let schema = Schema::parse(&schema, &Default::default()).unwrap();
+        assert!(schema.export_sdl().contains("union FooOrBar = Bar | Foo"));
+    }
+
+    #[test]
+    fn export_sdl_folds_in_extensions() {
+        let directive_def = "directive @tag(name: String!) repeatable on SCALAR | ENUM | UNION\n";
+
+        let compact = with_supergraph_boilerplate(&format!(
+            "{directive_def}{}",
+            r#"
+            type Query {
+                me: String
+            }
+            scalar Meta
+            enum Color {
+                RED
+            }
+            union FooOrBar = Foo
+            type Foo {
+                me: String
+            }
+            type Bar {
+                me: String
+            }
+
+            extend scalar Meta @tag(name: "pii")
+            extend enum Color {
+                BLUE
+            }
+            extend union FooOrBar = Bar
+            "#
+        ));
+        let spread_out = with_supergraph_boilerplate(&format!(
+            "{directive_def}{}",
+            r#"
+            type Query {
+                me: String
+            }
+            scalar Meta @tag(name: "pii")
+            enum Color {
+                RED
+                BLUE
+            }
+            union FooOrBar = Foo | Bar
+            type Foo {
+                me: String
+            }
+            type Bar {
+                me: String
+            }
+            "#
+        ));
+
+        let compact = Schema::parse(&compact, &Default::default()).unwrap();
+        let spread_out = Schema::parse(&spread_out, &Default::default()).unwrap();
+
+        assert_eq!(compact.export_sdl(), spread_out.export_sdl());
+    }
+
+    #[test]
+    fn export_sdl_includes_field_arguments() {
+        let schema = with_supergraph_boilerplate(
+            r#"
+            type Query {
+                users(filter: String): String
+            }
+            "#,
+        );
+        let schema = Schema::parse(&schema, &Default::default()).unwrap();
+
+        assert!(schema
+            .export_sdl()
+            .contains("users(filter: String): String"));
+    }
+
+    #[test]
+    fn export_sdl_includes_directive_definitions_and_schema_directives() {
+        let schema = with_supergraph_boilerplate(
+            r#"
+            type Query {
+                me: String
+            }
+            "#,
+        );
+        let schema = Schema::parse(&schema, &Default::default()).unwrap();
+        let exported = schema.export_sdl();
+
+        assert!(exported.contains("directive @core(feature: String!) repeatable on SCHEMA"));
+        assert!(exported.contains(r#"schema @core(feature: "https://specs.apollo.dev/core/v0.1") @core(feature: "https://specs.apollo.dev/join/v0.1") {"#));
+    }
+
+    #[test]
+    fn export_sdl_folds_in_extension_implements_clauses() {
+        let schema = with_supergraph_boilerplate(
+            r#"
+            type Query {
+                me: String
+            }
+            interface Baz {
+                me: String
+            }
+            type Foo {
+                me: String
+            }
+            extend type Foo implements Baz
+            "#,
+        );
+        let schema = Schema::parse(&schema, &Default::default()).unwrap();
+
+        // `Foo`'s `implements Baz` only comes from the `extend`, so if `export_sdl` only read
+        // the base type's `implements_interfaces()` this would be missing.
+        assert!(schema.export_sdl().contains("type Foo implements Baz"));
+    }
+
+    // Property-test harness for https://github.com/apollographql/router/issues/2269-style bugs:
+    // `Schema::parse` must never panic on well-formed-but-unusual input, only ever return `Ok`
+    // or a typed `SchemaError`. The same `generate_supergraph_sdl` generator backs the
+    // `cargo fuzz` target in `fuzz/fuzz_targets/parse_supergraph.rs`, which drives it with
+    // libFuzzer-supplied inputs; here it's exercised over a small fixed set of byte buffers so
+    // the property also runs as part of the regular test suite.
+    #[test]
+    fn parse_does_not_panic_on_generated_supergraphs() {
+        fn generated<'a>(names: impl Iterator<Item = &'a String>, prefix: &str) -> usize {
+            names.filter(|name| name.starts_with(prefix)).count()
+        }
+
+        for seed in 0u8..32 {
+            let bytes: Vec<u8> = (0..128)
+                .map(|i: u8| seed.wrapping_mul(31).wrapping_add(i))
+                .collect();
+            let mut u = arbitrary::Unstructured::new(&bytes);
+            let (sdl, counts) = match generate_supergraph_sdl(&mut u) {
+                Ok(generated) => generated,
+                // Ran out of entropy generating this particular seed; nothing to assert.
+                Err(_) => continue,
+            };
+
+            match Schema::parse(&sdl, &Default::default()) {
+                Ok(schema) => {
+                    // `introspection_types.graphql` and the supergraph boilerplate itself also
+                    // contribute type definitions, so only the generated (`Gen`-prefixed) ones
+                    // are counted.
+                    assert_eq!(
+                        generated(schema.object_types.keys(), "GenObject"),
+                        counts.object_types
+                    );
+                    assert_eq!(
+                        generated(schema.interfaces.keys(), "GenInterface"),
+                        counts.interfaces
+                    );
+                    assert_eq!(
+                        generated(schema.input_types.keys(), "GenInput"),
+                        counts.input_types
+                    );
+                    assert_eq!(generated(schema.enums.keys(), "GenEnum"), counts.enums);
+                }
+                Err(SchemaError::Parse(_) | SchemaError::Api(_)) => {}
+                Err(other) => panic!("unexpected schema error for generated SDL: {other}"),
+            }
+        }
+    }
+}
+
+/// Generator shared by the in-crate property test above and the `cargo fuzz` target in
+/// `fuzz/fuzz_targets/parse_supergraph.rs`. Kept outside `mod tests` so the `#[cfg(fuzzing)]`
+/// entry point below can reach it in a fuzz build, where `cfg(test)` isn't set.
+///
+/// `pub` (rather than `pub(crate)`, like the rest of this module's items) because a `pub fn`
+/// nested inside a private `mod` is still unreachable from outside this crate: every module on
+/// the path to `fuzz_parse_supergraph` has to be `pub` for `apollo-router-fuzz` to call it.
+#[cfg(any(test, fuzzing))]
+pub mod fuzz_support {
+    use super::*;
+
+    /// Counts of each kind of type definition [`generate_supergraph_sdl`] produced, so callers
+    /// can check `Schema::parse` saw exactly what was generated.
+    #[derive(Debug, Clone, Copy, Default)]
+    pub(crate) struct GeneratedCounts {
+        pub(crate) object_types: usize,
+        pub(crate) interfaces: usize,
+        pub(crate) input_types: usize,
+        pub(crate) enums: usize,
+        pub(crate) unions: usize,
+    }
+
+    /// Generates syntactically well-formed supergraph SDL: a random, non-empty set of
+    /// `join__Graph` subgraphs (each with a non-empty `url`, no duplicate names), plus a random
+    /// number of object, interface, input, enum, and union type definitions. Object and
+    /// interface types are `@join__type`-tagged against a randomly chosen subgraph (so
+    /// entity-key extraction runs over them too) and sometimes get an `extend` continuation (so
+    /// extension-folding runs over them too).
+    pub(crate) fn generate_supergraph_sdl(
+        u: &mut arbitrary::Unstructured<'_>,
+    ) -> arbitrary::Result<(String, GeneratedCounts)> {
+        let mut counts = GeneratedCounts::default();
+
+        let subgraph_count = u.int_in_range(1..=4)?;
+        let subgraphs: Vec<String> = (0..subgraph_count)
+            .map(|i| format!("SUBGRAPH{i}"))
+            .collect();
+        let join_graph_enum = subgraphs
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                format!(
+                    "    {name} @join__graph(name: \"subgraph{i}\", url: \"http://localhost:400{i}/graphql\")\n"
+                )
+            })
+            .join("");
+
+        let mut body = String::new();
+        let mut object_type_names = Vec::new();
+
+        for i in 0..u.int_in_range(0..=5)? {
+            let graph = &subgraphs[u.int_in_range(0..=subgraphs.len() - 1)?];
+            let name = format!("GenObject{i}");
+            body.push_str(&format!(
+                "type {name} @join__type(graph: {graph}) {{ field{i}: String }}\n"
+            ));
+            if u.ratio(1, 3)? {
+                body.push_str(&format!("extend type {name} {{ extra{i}: String }}\n"));
+            }
+            object_type_names.push(name);
+            counts.object_types += 1;
+        }
+
+        for i in 0..u.int_in_range(0..=3)? {
+            let graph = &subgraphs[u.int_in_range(0..=subgraphs.len() - 1)?];
+            let name = format!("GenInterface{i}");
+            body.push_str(&format!(
+                "interface {name} @join__type(graph: {graph}) {{ field{i}: String }}\n"
+            ));
+            if u.ratio(1, 3)? {
+                body.push_str(&format!("extend interface {name} {{ extra{i}: String }}\n"));
+            }
+            counts.interfaces += 1;
+        }
+
+        for i in 0..u.int_in_range(0..=3)? {
+            body.push_str(&format!("input GenInput{i} {{ field{i}: String }}\n"));
+            counts.input_types += 1;
+        }
+
+        for i in 0..u.int_in_range(0..=3)? {
+            body.push_str(&format!("enum GenEnum{i} {{ GEN_VALUE{i} }}\n"));
+            counts.enums += 1;
+        }
+
+        if !object_type_names.is_empty() {
+            for i in 0..u.int_in_range(0..=2)? {
+                let member = &object_type_names[u.int_in_range(0..=object_type_names.len() - 1)?];
+                body.push_str(&format!("union GenUnion{i} = {member}\n"));
+                counts.unions += 1;
+            }
+        }
+
+        let schema = format!(
+            r#"
+schema
+    @core(feature: "https://specs.apollo.dev/core/v0.1")
+    @core(feature: "https://specs.apollo.dev/join/v0.1") {{
+    query: Query
+}}
+directive @core(feature: String!) repeatable on SCHEMA
+directive @join__graph(name: String!, url: String!) on ENUM_VALUE
+directive @join__type(graph: join__Graph!, key: String) repeatable on OBJECT | INTERFACE
+enum join__Graph {{
+{join_graph_enum}}}
+
+type Query {{ me: String }}
+{body}
+"#
+        );
+
+        Ok((schema, counts))
+    }
+
+    /// `cargo fuzz` entry point: the crate's normal API surface is `pub(crate)`, so this is the
+    /// one function exposed `pub` (under `cfg(fuzzing)`, which `cargo fuzz` sets, so it doesn't
+    /// affect ordinary builds) for `fuzz/fuzz_targets/parse_supergraph.rs` to call.
+    #[cfg(fuzzing)]
+    pub fn fuzz_parse_supergraph(data: &[u8]) {
+        let mut u = arbitrary::Unstructured::new(data);
+        if let Ok((sdl, _counts)) = generate_supergraph_sdl(&mut u) {
+            let _ = Schema::parse(&sdl, &Default::default());
+        }
+    }
 }
+
+// Re-exported so `fuzz/fuzz_targets/parse_supergraph.rs` can call
+// `apollo_router::fuzz_parse_supergraph` directly, without knowing this lives in
+// `spec::schema::fuzz_support`. Still requires `spec` and `schema` to be `pub` (rather than
+// `pub(crate)`) the rest of the way up to the crate root.
+#[cfg(fuzzing)]
+pub use fuzz_support::fuzz_parse_supergraph;